@@ -1,7 +1,7 @@
 use crate::api::extraction_prompt::PromptTemplate;
 use derive_builder::Builder;
-use futures::stream::StreamExt;
-use serde::{Deserialize, Serialize};
+use futures::stream::{BoxStream, StreamExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::client::{ClientEnum, ReqClient};
@@ -19,6 +19,14 @@ pub enum Role {
     Assistant,
 }
 
+/// Constrains the assistant's reply format, mirroring OpenAI's `response_format` field.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    Text,
+    JsonObject,
+}
+
 #[derive(Builder, Default, Debug, Clone, Deserialize, Serialize)]
 #[builder(name = "ChatCompletionMessageRequestBuilder")]
 #[builder(pattern = "mutable")]
@@ -60,6 +68,27 @@ pub struct CreateChatRequest {
     pub logit_bias: Option<HashMap<String, serde_json::Value>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
+}
+
+impl CreateChatRequest {
+    /// Estimates the BPE tokens this request's `messages` will cost as a prompt,
+    /// including the per-message role/name overhead OpenAI charges for chat completions.
+    pub fn estimated_prompt_tokens(&self) -> usize {
+        let messages: Vec<(String, String, Option<String>)> = self
+            .messages
+            .iter()
+            .map(|message| {
+                (
+                    message.role.to_string(),
+                    message.content.clone(),
+                    message.name.clone(),
+                )
+            })
+            .collect();
+        crate::tokenizer::count_prompt_tokens(&self.model, &messages)
+    }
 }
 #[derive(Debug, Deserialize, Clone, Serialize)]
 pub struct Message {
@@ -89,6 +118,27 @@ pub struct ChatResponse {
     pub choices: Vec<ChatChoice>,
     pub usage: ChatUsage,
 }
+
+#[derive(Debug, Deserialize, Clone, Serialize, Default)]
+pub struct ChatCompletionChunkDelta {
+    pub role: Option<Role>,
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct ChatCompletionChunkChoice {
+    pub delta: ChatCompletionChunkDelta,
+    pub finish_reason: Option<String>,
+    pub index: u32,
+}
+
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct ChatCompletionChunk {
+    pub id: String,
+    pub object: String,
+    pub created: u32,
+    pub choices: Vec<ChatCompletionChunkChoice>,
+}
 pub struct Chat {
     client: ClientEnum,
 }
@@ -101,6 +151,41 @@ impl Chat {
     pub async fn create(&self, req: &CreateChatRequest) -> OpenAIResponse<ChatResponse> {
         self.client.post("/chat/completions", req).await
     }
+
+    pub async fn create_stream(
+        &self,
+        req: &CreateChatRequest,
+    ) -> Result<BoxStream<'static, Result<ChatCompletionChunk, OpenAIError>>, OpenAIError> {
+        let mut req = req.clone();
+        req.stream = Some(true);
+        self.client.post_stream("/chat/completions", &req).await
+    }
+
+    /// Validates that the estimated prompt tokens plus `req.max_tokens` fit within
+    /// `context_window` before making the request, returning
+    /// `OpenAIError::InvalidArgument` with the overflow amount instead of a doomed call.
+    pub async fn create_within_budget(
+        &self,
+        req: &CreateChatRequest,
+        context_window: usize,
+    ) -> OpenAIResponse<ChatResponse> {
+        let prompt_tokens = req.estimated_prompt_tokens();
+        let max_tokens = req.max_tokens.unwrap_or(0) as usize;
+        let requested = prompt_tokens + max_tokens;
+        if requested > context_window {
+            return Err(OpenAIError::InvalidArgument(format!(
+                "request needs {} tokens ({} prompt + {} max_tokens), which is {} over the {} token context window",
+                requested,
+                prompt_tokens,
+                max_tokens,
+                requested - context_window,
+                context_window
+            )));
+        }
+
+        self.create(req).await
+    }
+
     pub async fn create_with_template(
         &self,
         template: PromptTemplate,
@@ -125,4 +210,98 @@ impl Chat {
 
         self.create(&req).await
     }
+
+    /// Like [`Chat::create_with_template`], but puts the model in JSON mode using the
+    /// template's JSON schema and deserializes the assistant's reply directly into `T`.
+    pub async fn extract_with_template<T: DeserializeOwned>(
+        &self,
+        template: PromptTemplate,
+        objects: HashMap<String, String>,
+        model: &str,
+    ) -> Result<T, OpenAIError> {
+        let prompt = template.generate_prompt(objects);
+
+        let message = ChatCompletionMessage {
+            role: Role::User,
+            content: prompt,
+            name: None,
+        };
+
+        let req = CreateChatRequestBuilder::default()
+            .model(model)
+            .messages(vec![message])
+            .response_format(ResponseFormat::JsonObject)
+            .build()
+            .map_err(|_| {
+                OpenAIError::InvalidArgument("Failed to build CreateChatRequest".into())
+            })?;
+
+        let response = self.create(&req).await?;
+        parse_structured_reply(&response)
+    }
+}
+
+/// Deserializes the first choice's message content as `T`, the shared tail of
+/// [`Chat::extract_with_template`]'s JSON-mode response handling.
+fn parse_structured_reply<T: DeserializeOwned>(response: &ChatResponse) -> Result<T, OpenAIError> {
+    let content = response
+        .choices
+        .first()
+        .map(|choice| choice.message.content.as_str())
+        .ok_or_else(|| OpenAIError::InvalidArgument("chat response contained no choices".into()))?;
+
+    serde_json::from_str(content).map_err(OpenAIError::JSONDeserialize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Extracted {
+        name: String,
+        age: u8,
+    }
+
+    fn response_with_content(content: &str) -> ChatResponse {
+        ChatResponse {
+            id: "chatcmpl-test".into(),
+            object: "chat.completion".into(),
+            created: 0,
+            choices: vec![ChatChoice {
+                message: ChatCompletionMessage {
+                    role: Role::Assistant,
+                    content: content.into(),
+                    name: None,
+                },
+                finish_reason: "stop".into(),
+                index: 0,
+            }],
+            usage: ChatUsage {
+                prompt_tokens: 0,
+                completion_tokens: 0,
+                total_tokens: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn parse_structured_reply_deserializes_valid_json_content() {
+        let response = response_with_content(r#"{"name": "Alice", "age": 30}"#);
+        let extracted: Extracted = parse_structured_reply(&response).unwrap();
+        assert_eq!(
+            extracted,
+            Extracted {
+                name: "Alice".into(),
+                age: 30
+            }
+        );
+    }
+
+    #[test]
+    fn parse_structured_reply_reports_malformed_json_as_deserialize_error() {
+        let response = response_with_content("not json");
+        let result: Result<Extracted, OpenAIError> = parse_structured_reply(&response);
+        assert!(matches!(result, Err(OpenAIError::JSONDeserialize(_))));
+    }
 }
@@ -27,6 +27,31 @@ impl PromptTemplate {
         }
     }
 
+    /// Builds a strict JSON-schema object describing this template's fields, suitable
+    /// for pairing with `response_format: json_object` so the model's reply can be
+    /// deserialized directly instead of parsed out of free text.
+    pub fn json_schema(&self) -> serde_json::Value {
+        let mut properties = serde_json::Map::new();
+        let mut required = Vec::new();
+
+        for field in &self.fields {
+            properties.insert(
+                field.name.clone(),
+                serde_json::json!({
+                    "type": json_type(&field.field_type),
+                    "description": field.description,
+                }),
+            );
+            required.push(serde_json::Value::String(field.name.clone()));
+        }
+
+        serde_json::json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        })
+    }
+
     pub fn generate_prompt(&self, objects: HashMap<String, String>) -> String {
         let mut prompt = String::new();
 
@@ -38,14 +63,13 @@ impl PromptTemplate {
             prompt.push_str(&format!("User Input: {}\n", user_input));
         }
 
-        prompt.push_str("JSON INSTRUCT with Fields:\n");
+        prompt.push_str("Respond with a single JSON object matching this schema:\n");
+        prompt.push_str(&self.json_schema().to_string());
+        prompt.push('\n');
 
         for field in &self.fields {
             if let Some(value) = objects.get(&field.name) {
-                prompt.push_str(&format!(
-                    "{} ({}): {}\n",
-                    field.name, field.field_type, value
-                ));
+                prompt.push_str(&format!("{} ({}): {}\n", field.name, field.field_type, value));
             } else {
                 prompt.push_str(&format!("{} ({}): \n", field.name, field.field_type));
             }
@@ -54,3 +78,65 @@ impl PromptTemplate {
         prompt
     }
 }
+
+/// Maps a [`Field::field_type`] to the JSON Schema type it should be described as.
+/// Unrecognized types fall back to `"string"` rather than rejecting the template.
+fn json_type(field_type: &str) -> &'static str {
+    match field_type {
+        "number" | "float" | "f32" | "f64" => "number",
+        "integer" | "int" | "i32" | "i64" | "u32" | "u64" => "integer",
+        "boolean" | "bool" => "boolean",
+        "array" | "vec" | "list" => "array",
+        "object" | "map" => "object",
+        _ => "string",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template() -> PromptTemplate {
+        PromptTemplate::new(
+            vec![
+                Field {
+                    name: "age".into(),
+                    field_type: "integer".into(),
+                    description: "the person's age".into(),
+                },
+                Field {
+                    name: "nickname".into(),
+                    field_type: "string".into(),
+                    description: "what they go by".into(),
+                },
+            ],
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn json_schema_marks_every_field_required_with_its_mapped_type() {
+        let schema = template().json_schema();
+
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["properties"]["age"]["type"], "integer");
+        assert_eq!(schema["properties"]["nickname"]["type"], "string");
+        assert_eq!(
+            schema["required"],
+            serde_json::json!(["age", "nickname"])
+        );
+    }
+
+    #[test]
+    fn generate_prompt_lists_every_field_even_when_unset() {
+        let mut objects = HashMap::new();
+        objects.insert("age".to_string(), "42".to_string());
+
+        let prompt = template().generate_prompt(objects);
+
+        assert!(prompt.contains("age (integer): 42\n"));
+        // A field with no supplied value still gets a placeholder line, not silent omission.
+        assert!(prompt.contains("nickname (string): \n"));
+    }
+}
@@ -1,12 +1,124 @@
 use crate::core::response_wrapper::{ApiErrorResponse, OpenAIError};
 use async_trait::async_trait;
-use reqwest::{header::HeaderMap, Client, Method, RequestBuilder};
+use bytes::Bytes;
+use futures::stream::{BoxStream, StreamExt};
+use rand::Rng;
+use reqwest::{header::HeaderMap, Client, Method, RequestBuilder, Response, StatusCode};
 
 use serde::{de::DeserializeOwned, Serialize};
 use std::fmt::Debug;
+use std::time::{Duration, SystemTime};
 
 pub const ORGANIZATION_HEADER: &str = "OpenAI-Organization";
 
+/// Retry behavior for transient failures (HTTP 429 and 5xx).
+///
+/// When the API supplies a `Retry-After` header the delay is taken from it verbatim;
+/// otherwise the delay grows as `base_delay * 2^attempt`, capped at `max_delay`, with a
+/// small random jitter added on top so concurrent callers don't retry in lockstep.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(SystemTime::now()).unwrap_or_default())
+}
+
+fn backoff_delay(attempt: u32, policy: &RetryPolicy) -> Duration {
+    let exponential = policy
+        .base_delay
+        .saturating_mul(2u32.saturating_pow(attempt));
+    let capped = exponential.min(policy.max_delay);
+    let jitter = Duration::from_millis(rand::thread_rng().gen_range(0..=250));
+    capped + jitter
+}
+
+/// Connection-level configuration shared by every [`ReqClient`] implementation.
+///
+/// `proxy` accepts both `https://` and `socks5://` URLs. Timeouts default to
+/// reqwest's own defaults (no connect timeout, no overall timeout) when left unset.
+#[derive(Debug, Clone, Default)]
+pub struct ClientOptions {
+    pub proxy: Option<String>,
+    pub connect_timeout: Option<Duration>,
+    pub timeout: Option<Duration>,
+    pub default_headers: HeaderMap,
+    pub retry_policy: RetryPolicy,
+}
+
+impl ClientOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn default_headers(mut self, default_headers: HeaderMap) -> Self {
+        self.default_headers = default_headers;
+        self
+    }
+
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    fn build_client(&self) -> Result<Client, OpenAIError> {
+        let mut builder = Client::builder().default_headers(self.default_headers.clone());
+
+        if let Some(proxy) = &self.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|err| OpenAIError::InvalidArgument(format!("invalid proxy: {err}")))?;
+            builder = builder.proxy(proxy);
+        }
+        if let Some(connect_timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        builder
+            .build()
+            .map_err(|err| OpenAIError::InvalidArgument(format!("failed to build client: {err}")))
+    }
+}
+
 #[async_trait]
 pub trait ReqClient: Sync + Send {
     fn headers(&self) -> HeaderMap;
@@ -24,13 +136,21 @@ pub trait ReqClient: Sync + Send {
     where
         T: DeserializeOwned + Debug + Send,
         F: Serialize + Send + Sync;
+
+    async fn post_stream<T, F>(
+        &self,
+        route: &str,
+        json: &F,
+    ) -> Result<BoxStream<'static, Result<T, OpenAIError>>, OpenAIError>
+    where
+        T: DeserializeOwned + Debug + Send + 'static,
+        F: Serialize + Send + Sync;
 }
 
-async fn resolve_response<T>(request: RequestBuilder) -> Result<T, OpenAIError>
+async fn resolve_response<T>(response: Response) -> Result<T, OpenAIError>
 where
     T: DeserializeOwned + Debug + Send,
 {
-    let response = request.send().await?;
     let status = response.status();
     let bytes = response.bytes().await?;
     if !status.is_success() {
@@ -42,34 +162,136 @@ where
     Ok(data)
 }
 
+const SSE_DONE: &str = "[DONE]";
+
+/// The result of parsing one buffered line out of an SSE byte stream.
+enum SseLine<T> {
+    /// Not a `data: ` payload (e.g. blank line); keep reading, nothing to emit.
+    Skip,
+    /// The `data: [DONE]` sentinel; the stream must end here, not on connection close.
+    Done,
+    /// A parsed (or unparseable) `data: ` payload.
+    Data(Result<T, OpenAIError>),
+}
+
+/// Parses one SSE `data: ...` line out of `buf`, if a full line is buffered.
+/// Returns `None` if more bytes are needed to complete a line.
+fn take_sse_event<T>(buf: &mut Vec<u8>) -> Option<SseLine<T>>
+where
+    T: DeserializeOwned,
+{
+    let pos = buf.iter().position(|&b| b == b'\n')?;
+    let line: Vec<u8> = buf.drain(..=pos).collect();
+    let line = String::from_utf8_lossy(&line);
+    let line = line.trim();
+
+    let Some(data) = line.strip_prefix("data: ") else {
+        return Some(SseLine::Skip);
+    };
+    if data == SSE_DONE {
+        return Some(SseLine::Done);
+    }
+    Some(SseLine::Data(
+        serde_json::from_str(data).map_err(OpenAIError::JSONDeserialize),
+    ))
+}
+
+/// Turns a raw SSE byte stream into a stream of decoded events, terminating as soon as
+/// the `data: [DONE]` sentinel is seen rather than waiting on the underlying connection
+/// to close (which a proxy or keep-alive hop may delay indefinitely).
+fn sse_stream<T>(
+    byte_stream: impl futures::Stream<Item = reqwest::Result<Bytes>> + Unpin + Send + 'static,
+) -> BoxStream<'static, Result<T, OpenAIError>>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    futures::stream::unfold(
+        (byte_stream, Vec::new()),
+        |(mut byte_stream, mut buf)| async move {
+            loop {
+                while let Some(line) = take_sse_event::<T>(&mut buf) {
+                    match line {
+                        SseLine::Skip => continue,
+                        SseLine::Done => return None,
+                        SseLine::Data(data) => return Some((data, (byte_stream, buf))),
+                    }
+                }
+
+                match byte_stream.next().await {
+                    Some(Ok(bytes)) => buf.extend_from_slice(&bytes),
+                    Some(Err(err)) => {
+                        return Some((Err(OpenAIError::Reqwest(err)), (byte_stream, buf)))
+                    }
+                    None => return None,
+                }
+            }
+        },
+    )
+    .boxed()
+}
+
+async fn resolve_stream<T>(
+    response: Response,
+) -> Result<BoxStream<'static, Result<T, OpenAIError>>, OpenAIError>
+where
+    T: DeserializeOwned + Debug + Send + 'static,
+{
+    let status = response.status();
+    if !status.is_success() {
+        let bytes = response.bytes().await?;
+        let api_error: ApiErrorResponse =
+            serde_json::from_slice(bytes.as_ref()).map_err(OpenAIError::JSONDeserialize)?;
+        return Err(OpenAIError::ApiError(api_error));
+    }
+
+    Ok(sse_stream(response.bytes_stream()))
+}
+
 pub struct ClientBase {
     pub api_key: String,
     pub base_url: String,
+    client: Client,
+    retry_policy: RetryPolicy,
 }
 
 impl ClientBase {
-    pub fn new(api_key: String, base_url: String) -> Self {
-        Self { api_key, base_url }
+    pub fn new(api_key: String, base_url: String) -> Result<Self, OpenAIError> {
+        Self::with_options(api_key, base_url, ClientOptions::default())
+    }
+
+    pub fn with_options(
+        api_key: String,
+        base_url: String,
+        options: ClientOptions,
+    ) -> Result<Self, OpenAIError> {
+        let retry_policy = options.retry_policy.clone();
+        let client = options.build_client()?;
+        Ok(Self {
+            api_key,
+            base_url,
+            client,
+            retry_policy,
+        })
     }
 
-    fn headers(&self) -> HeaderMap {
+    pub(crate) fn headers(&self) -> HeaderMap {
         HeaderMap::new()
     }
 
-    fn api_key(&self) -> &str {
+    pub(crate) fn api_key(&self) -> &str {
         &self.api_key
     }
 
-    fn api_base(&self) -> &str {
+    pub(crate) fn api_base(&self) -> &str {
         &self.base_url
     }
 
-    fn request<F>(&self, method: Method, route: &str, builder: F) -> RequestBuilder
+    fn request<F>(&self, method: Method, route: &str, builder: &F) -> RequestBuilder
     where
-        F: FnOnce(RequestBuilder) -> RequestBuilder + Send,
+        F: Fn(RequestBuilder) -> RequestBuilder,
     {
-        let client = Client::new();
-        let mut request = client
+        let mut request = self
+            .client
             .request(method, format!("{}{}", self.api_base(), route))
             .headers(self.headers())
             .bearer_auth(self.api_key());
@@ -77,184 +299,375 @@ impl ClientBase {
         request
     }
 
-    async fn get<T, F>(&self, route: &str, query: &F) -> Result<T, OpenAIError>
+    /// Sends the request, rebuilding and retrying it on a 429/5xx response per
+    /// `self.retry_policy`. The final response (success or exhausted failure) is
+    /// handed back unparsed so callers can still branch on status themselves.
+    async fn send_with_retry<F>(
+        &self,
+        method: Method,
+        route: &str,
+        builder: F,
+    ) -> Result<Response, OpenAIError>
     where
-        T: DeserializeOwned + Debug + Send,
-        F: Serialize + Send + Sync,
+        F: Fn(RequestBuilder) -> RequestBuilder + Send + Sync,
     {
-        let request = self.request(Method::GET, route, |req| req.query(query));
-        resolve_response(request).await
+        let mut attempt = 0;
+        loop {
+            let request = self.request(method.clone(), route, &builder);
+            let response = request.send().await?;
+            let status = response.status();
+
+            if status.is_success()
+                || !is_retryable_status(status)
+                || attempt >= self.retry_policy.max_retries
+            {
+                return Ok(response);
+            }
+
+            let delay = retry_after(&response)
+                .unwrap_or_else(|| backoff_delay(attempt, &self.retry_policy));
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
     }
 
-    async fn post<T, F>(&self, route: &str, json: &F) -> Result<T, OpenAIError>
+    pub(crate) async fn get<T, F>(&self, route: &str, query: &F) -> Result<T, OpenAIError>
     where
         T: DeserializeOwned + Debug + Send,
         F: Serialize + Send + Sync,
     {
-        let request = self.request(Method::POST, route, |req| req.json(json));
-        resolve_response(request).await
-    }
-}
-
-pub struct OpenAI {
-    base: ClientBase,
-    pub org_id: Option<String>,
-}
-
-impl OpenAI {
-    pub fn new(api_key: String, org_id: Option<String>) -> Self {
-        let base_url = "https://api.openai.com/v1".to_string();
-        Self {
-            base: ClientBase::new(api_key, base_url),
-            org_id,
-        }
-    }
-}
-
-#[async_trait]
-impl ReqClient for OpenAI {
-    fn headers(&self) -> HeaderMap {
-        let mut headers = self.base.headers();
-        if let Some(org_id) = &self.org_id {
-            headers.insert(ORGANIZATION_HEADER, org_id.parse().unwrap());
-        }
-        headers
+        let response = self
+            .send_with_retry(Method::GET, route, |req| req.query(query))
+            .await?;
+        resolve_response(response).await
     }
 
-    fn api_key(&self) -> &str {
-        self.base.api_key()
-    }
-
-    fn api_base(&self) -> String {
-        self.base.api_base().to_string()
-    }
-
-    async fn get<T, F>(&self, route: &str, query: &F) -> Result<T, OpenAIError>
+    pub(crate) async fn post<T, F>(&self, route: &str, json: &F) -> Result<T, OpenAIError>
     where
         T: DeserializeOwned + Debug + Send,
         F: Serialize + Send + Sync,
     {
-        self.base.get(route, query).await
+        let response = self
+            .send_with_retry(Method::POST, route, |req| req.json(json))
+            .await?;
+        resolve_response(response).await
     }
 
-    async fn post<T, F>(&self, route: &str, json: &F) -> Result<T, OpenAIError>
+    pub(crate) async fn post_stream<T, F>(
+        &self,
+        route: &str,
+        json: &F,
+    ) -> Result<BoxStream<'static, Result<T, OpenAIError>>, OpenAIError>
     where
-        T: DeserializeOwned + Debug + Send,
+        T: DeserializeOwned + Debug + Send + 'static,
         F: Serialize + Send + Sync,
     {
-        self.base.post(route, json).await
+        let response = self
+            .send_with_retry(Method::POST, route, |req| req.json(json))
+            .await?;
+        resolve_stream(response).await
     }
 }
-pub struct AzureOpenAI {
-    base: ClientBase,
-    pub resource_name: String,
-    pub deployment_id: String,
-    pub api_version: String,
+
+/// Builds a concrete [`ReqClient`] from its deserialized config counterpart.
+///
+/// Implemented by each provider's `*Config` struct so [`register_client!`] can turn a
+/// [`ClientConfig`] loaded from a config file into the matching [`ClientEnum`] variant.
+pub trait ClientConfigBuild {
+    type Client;
+
+    fn build(self) -> Result<Self::Client, OpenAIError>;
 }
 
-impl AzureOpenAI {
-    pub fn new(
-        api_key: String,
-        resource_name: String,
-        deployment_id: String,
-        api_version: String,
-    ) -> Self {
-        let base_url = format!("https://{}.openai.azure.com", resource_name);
-        Self {
-            base: ClientBase::new(api_key, base_url),
-            resource_name,
-            deployment_id,
-            api_version,
+/// Generates [`ClientEnum`] and [`ClientConfig`] from a list of
+/// `(module, variant, tag, ConfigStruct, ClientStruct)` provider declarations, where
+/// `tag` is the literal `type` value [`ClientConfig`] matches in a config file.
+///
+/// Adding a new OpenAI-API-compatible provider only requires a module implementing
+/// [`ReqClient`] and [`ClientConfigBuild`], plus one entry in this macro invocation -
+/// every dispatch site (`headers`/`api_key`/`api_base`/`get`/`post`/`post_stream`) and
+/// the config-driven constructor are generated from it.
+#[macro_export]
+macro_rules! register_client {
+    ($(($module:path, $variant:ident, $tag:literal, $config:ident, $client:ident)),+ $(,)?) => {
+        $(use $module::{$config, $client};)+
+
+        pub enum ClientEnum {
+            $($variant($client),)+
         }
-    }
 
-    fn route_with_deployment(&self, route: &str) -> String {
-        format!(
-            "/openai/deployments/{}/{}?api-version={}",
-            self.deployment_id, route, self.api_version
-        )
-    }
+        impl ClientEnum {
+            pub fn headers(&self) -> $crate::client::HeaderMap {
+                match self {
+                    $(ClientEnum::$variant(client) => $crate::client::ReqClient::headers(client),)+
+                }
+            }
+
+            pub fn api_key(&self) -> &str {
+                match self {
+                    $(ClientEnum::$variant(client) => $crate::client::ReqClient::api_key(client),)+
+                }
+            }
+
+            pub fn api_base(&self) -> String {
+                match self {
+                    $(ClientEnum::$variant(client) => $crate::client::ReqClient::api_base(client),)+
+                }
+            }
+
+            pub async fn get<T, F>(&self, route: &str, query: &F) -> Result<T, $crate::core::response_wrapper::OpenAIError>
+            where
+                T: serde::de::DeserializeOwned + std::fmt::Debug + Send,
+                F: serde::Serialize + Send + Sync,
+            {
+                match self {
+                    $(ClientEnum::$variant(client) => $crate::client::ReqClient::get(client, route, query).await,)+
+                }
+            }
+
+            pub async fn post<T, F>(&self, route: &str, json: &F) -> Result<T, $crate::core::response_wrapper::OpenAIError>
+            where
+                T: serde::de::DeserializeOwned + std::fmt::Debug + Send,
+                F: serde::Serialize + Send + Sync,
+            {
+                match self {
+                    $(ClientEnum::$variant(client) => $crate::client::ReqClient::post(client, route, json).await,)+
+                }
+            }
+
+            pub async fn post_stream<T, F>(
+                &self,
+                route: &str,
+                json: &F,
+            ) -> Result<
+                futures::stream::BoxStream<'static, Result<T, $crate::core::response_wrapper::OpenAIError>>,
+                $crate::core::response_wrapper::OpenAIError,
+            >
+            where
+                T: serde::de::DeserializeOwned + std::fmt::Debug + Send + 'static,
+                F: serde::Serialize + Send + Sync,
+            {
+                match self {
+                    $(ClientEnum::$variant(client) => $crate::client::ReqClient::post_stream(client, route, json).await,)+
+                }
+            }
+        }
+
+        /// A client definition as loaded from a config file, tagged by provider `type`.
+        #[derive(serde::Deserialize)]
+        #[serde(tag = "type")]
+        pub enum ClientConfig {
+            $(#[serde(rename = $tag)] $variant($config),)+
+        }
+
+        impl ClientConfig {
+            pub fn build(self) -> Result<ClientEnum, $crate::core::response_wrapper::OpenAIError> {
+                match self {
+                    $(ClientConfig::$variant(config) => {
+                        $crate::client::ClientConfigBuild::build(config).map(ClientEnum::$variant)
+                    })+
+                }
+            }
+        }
+    };
 }
 
-#[async_trait]
-impl ReqClient for AzureOpenAI {
-    fn headers(&self) -> HeaderMap {
-        self.base.headers()
-    }
+register_client! {
+    (crate::providers::openai, OpenAI, "open_ai", OpenAIConfig, OpenAI),
+    (crate::providers::azure, AzureOpenAI, "azure_open_ai", AzureOpenAIConfig, AzureOpenAI),
+}
 
-    fn api_key(&self) -> &str {
-        self.base.api_key()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct TestChunk {
+        value: u32,
     }
 
-    fn api_base(&self) -> String {
-        self.base.api_base().to_string()
+    #[test]
+    fn take_sse_event_buffers_across_partial_chunks() {
+        let mut buf = Vec::new();
+
+        // A chunk boundary lands mid-JSON: no full line is buffered yet.
+        buf.extend_from_slice(b"data: {\"value\"");
+        assert!(take_sse_event::<TestChunk>(&mut buf).is_none());
+
+        // The rest of the line arrives, followed by the blank line SSE uses as framing.
+        buf.extend_from_slice(b":1}\n\n");
+        let chunk = match take_sse_event::<TestChunk>(&mut buf).expect("line is buffered") {
+            SseLine::Data(data) => data.expect("valid json"),
+            _ => panic!("expected a data line"),
+        };
+        assert_eq!(chunk, TestChunk { value: 1 });
+
+        assert!(matches!(
+            take_sse_event::<TestChunk>(&mut buf).expect("blank line is buffered"),
+            SseLine::Skip
+        ));
+        assert!(take_sse_event::<TestChunk>(&mut buf).is_none());
+
+        // A second event, again split mid-JSON across two chunk pushes.
+        buf.extend_from_slice(b"data: {\"value\":2");
+        assert!(take_sse_event::<TestChunk>(&mut buf).is_none());
+        buf.extend_from_slice(b"}\n");
+        let chunk = match take_sse_event::<TestChunk>(&mut buf).expect("line is buffered") {
+            SseLine::Data(data) => data.expect("valid json"),
+            _ => panic!("expected a data line"),
+        };
+        assert_eq!(chunk, TestChunk { value: 2 });
+
+        // The trailing [DONE] sentinel is reported distinctly from a line to skip.
+        buf.extend_from_slice(b"data: [DONE]\n");
+        assert!(matches!(
+            take_sse_event::<TestChunk>(&mut buf).expect("line is buffered"),
+            SseLine::Done
+        ));
+        assert!(take_sse_event::<TestChunk>(&mut buf).is_none());
     }
 
-    async fn get<T, F>(&self, route: &str, query: &F) -> Result<T, OpenAIError>
-    where
-        T: DeserializeOwned + Debug + Send,
-        F: Serialize + Send + Sync,
-    {
-        let route = self.route_with_deployment(route);
-        self.base.get(&route, query).await
+    #[tokio::test]
+    async fn sse_stream_terminates_on_done_without_waiting_for_the_source_to_close() {
+        let chunk = Bytes::from_static(b"data: {\"value\":1}\n\ndata: [DONE]\n\n");
+        // `pending()` never resolves, simulating a keep-alive connection that doesn't
+        // close right after `[DONE]`; the stream must end on the sentinel regardless.
+        let source = futures::stream::once(async move { Ok(chunk) })
+            .chain(futures::stream::pending())
+            .boxed();
+
+        let mut stream = sse_stream::<TestChunk>(source);
+
+        let first = tokio::time::timeout(Duration::from_secs(2), stream.next())
+            .await
+            .expect("stream should not hang waiting for more bytes")
+            .expect("one event before [DONE]")
+            .expect("valid json");
+        assert_eq!(first, TestChunk { value: 1 });
+
+        let done = tokio::time::timeout(Duration::from_secs(2), stream.next())
+            .await
+            .expect("stream should terminate immediately on [DONE], not hang on the source");
+        assert!(done.is_none());
     }
 
-    async fn post<T, F>(&self, route: &str, json: &F) -> Result<T, OpenAIError>
-    where
-        T: DeserializeOwned + Debug + Send,
-        F: Serialize + Send + Sync,
-    {
-        let route = self.route_with_deployment(route);
-        self.base.post(&route, json).await
+    #[test]
+    fn backoff_delay_grows_exponentially_and_caps() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+        };
+        let jitter_ceiling = Duration::from_millis(250);
+
+        let first = backoff_delay(0, &policy);
+        assert!(first >= Duration::from_millis(100) && first <= Duration::from_millis(100) + jitter_ceiling);
+
+        let second = backoff_delay(1, &policy);
+        assert!(second >= Duration::from_millis(200) && second <= Duration::from_millis(200) + jitter_ceiling);
+
+        // Attempt 3 would be 800ms uncapped, so it must clamp to max_delay instead.
+        let capped = backoff_delay(3, &policy);
+        assert!(capped >= policy.max_delay && capped <= policy.max_delay + jitter_ceiling);
     }
-}
 
-pub enum ClientEnum {
-    OpenAI(OpenAI),
-    AzureOpenAI(AzureOpenAI),
-}
+    #[tokio::test]
+    async fn retry_after_parses_numeric_seconds() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .respond_with(wiremock::ResponseTemplate::new(429).insert_header("Retry-After", "2"))
+            .mount(&server)
+            .await;
 
-impl ClientEnum {
-    pub fn headers(&self) -> HeaderMap {
-        match self {
-            ClientEnum::OpenAI(client) => client.headers(),
-            ClientEnum::AzureOpenAI(client) => client.headers(),
-        }
+        let response = reqwest::get(server.uri()).await.unwrap();
+        assert_eq!(retry_after(&response), Some(Duration::from_secs(2)));
     }
 
-    pub fn api_key(&self) -> &str {
-        match self {
-            ClientEnum::OpenAI(client) => client.api_key(),
-            ClientEnum::AzureOpenAI(client) => client.api_key(),
-        }
+    #[tokio::test]
+    async fn retry_after_http_date_in_the_past_does_not_underflow() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(429)
+                    .insert_header("Retry-After", "Sun, 06 Nov 1994 08:49:37 GMT"),
+            )
+            .mount(&server)
+            .await;
+
+        let response = reqwest::get(server.uri()).await.unwrap();
+        // A Retry-After date in the past must not panic/underflow; it just means no extra wait.
+        assert_eq!(retry_after(&response), Some(Duration::ZERO));
     }
 
-    pub fn api_base(&self) -> String {
-        match self {
-            ClientEnum::OpenAI(client) => client.api_base(),
-            ClientEnum::AzureOpenAI(client) => client.api_base(),
-        }
+    #[tokio::test]
+    async fn send_with_retry_stops_after_exactly_max_retries() {
+        let server = wiremock::MockServer::start().await;
+        let policy = RetryPolicy {
+            max_retries: 2,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+        };
+
+        // 1 initial attempt + `max_retries` retries = 3 total requests before giving up.
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .respond_with(wiremock::ResponseTemplate::new(500))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let client = ClientBase::with_options(
+            "key".into(),
+            server.uri(),
+            ClientOptions::new().retry_policy(policy),
+        )
+        .unwrap();
+
+        let response = client
+            .send_with_retry(Method::GET, "/", |req| req)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        server.verify().await;
     }
 
-    pub async fn get<T, F>(&self, route: &str, query: &F) -> Result<T, OpenAIError>
-    where
-        T: DeserializeOwned + Debug + Send,
-        F: Serialize + Send + Sync,
-    {
-        match self {
-            ClientEnum::OpenAI(client) => client.get(route, query).await,
-            ClientEnum::AzureOpenAI(client) => client.get(route, query).await,
-        }
+    #[test]
+    fn build_client_accepts_proxy_and_timeouts() {
+        let options = ClientOptions::new()
+            .proxy("https://proxy.example.com")
+            .connect_timeout(Duration::from_secs(1))
+            .timeout(Duration::from_secs(5));
+        assert!(options.build_client().is_ok());
     }
 
-    pub async fn post<T, F>(&self, route: &str, json: &F) -> Result<T, OpenAIError>
-    where
-        T: DeserializeOwned + Debug + Send,
-        F: Serialize + Send + Sync,
-    {
-        match self {
-            ClientEnum::OpenAI(client) => client.post(route, json).await,
-            ClientEnum::AzureOpenAI(client) => client.post(route, json).await,
-        }
+    #[test]
+    fn build_client_rejects_an_invalid_proxy_url() {
+        let options = ClientOptions::new().proxy("not a valid proxy url");
+        assert!(matches!(
+            options.build_client(),
+            Err(OpenAIError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn client_config_deserializes_by_its_documented_tag() {
+        let config: ClientConfig = serde_json::from_value(serde_json::json!({
+            "type": "open_ai",
+            "api_key": "key",
+            "org_id": null,
+        }))
+        .expect("\"open_ai\" is the documented tag for OpenAIConfig");
+        assert!(matches!(config, ClientConfig::OpenAI(_)));
+
+        let config: ClientConfig = serde_json::from_value(serde_json::json!({
+            "type": "azure_open_ai",
+            "api_key": "key",
+            "resource_name": "res",
+            "deployment_id": "dep",
+            "api_version": "2024-02-01",
+        }))
+        .expect("\"azure_open_ai\" is the documented tag for AzureOpenAIConfig");
+        assert!(matches!(config, ClientConfig::AzureOpenAI(_)));
     }
 }
@@ -0,0 +1,122 @@
+use crate::client::{ClientBase, ClientConfigBuild, ClientOptions, ReqClient};
+use crate::core::response_wrapper::OpenAIError;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use reqwest::header::HeaderMap;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fmt::Debug;
+
+pub struct AzureOpenAI {
+    base: ClientBase,
+    pub resource_name: String,
+    pub deployment_id: String,
+    pub api_version: String,
+}
+
+impl AzureOpenAI {
+    pub fn new(
+        api_key: String,
+        resource_name: String,
+        deployment_id: String,
+        api_version: String,
+    ) -> Result<Self, OpenAIError> {
+        Self::with_options(
+            api_key,
+            resource_name,
+            deployment_id,
+            api_version,
+            ClientOptions::default(),
+        )
+    }
+
+    pub fn with_options(
+        api_key: String,
+        resource_name: String,
+        deployment_id: String,
+        api_version: String,
+        options: ClientOptions,
+    ) -> Result<Self, OpenAIError> {
+        let base_url = format!("https://{}.openai.azure.com", resource_name);
+        Ok(Self {
+            base: ClientBase::with_options(api_key, base_url, options)?,
+            resource_name,
+            deployment_id,
+            api_version,
+        })
+    }
+
+    fn route_with_deployment(&self, route: &str) -> String {
+        format!(
+            "/openai/deployments/{}/{}?api-version={}",
+            self.deployment_id, route, self.api_version
+        )
+    }
+}
+
+#[async_trait]
+impl ReqClient for AzureOpenAI {
+    fn headers(&self) -> HeaderMap {
+        self.base.headers()
+    }
+
+    fn api_key(&self) -> &str {
+        self.base.api_key()
+    }
+
+    fn api_base(&self) -> String {
+        self.base.api_base().to_string()
+    }
+
+    async fn get<T, F>(&self, route: &str, query: &F) -> Result<T, OpenAIError>
+    where
+        T: DeserializeOwned + Debug + Send,
+        F: Serialize + Send + Sync,
+    {
+        let route = self.route_with_deployment(route);
+        self.base.get(&route, query).await
+    }
+
+    async fn post<T, F>(&self, route: &str, json: &F) -> Result<T, OpenAIError>
+    where
+        T: DeserializeOwned + Debug + Send,
+        F: Serialize + Send + Sync,
+    {
+        let route = self.route_with_deployment(route);
+        self.base.post(&route, json).await
+    }
+
+    async fn post_stream<T, F>(
+        &self,
+        route: &str,
+        json: &F,
+    ) -> Result<BoxStream<'static, Result<T, OpenAIError>>, OpenAIError>
+    where
+        T: DeserializeOwned + Debug + Send + 'static,
+        F: Serialize + Send + Sync,
+    {
+        let route = self.route_with_deployment(route);
+        self.base.post_stream(&route, json).await
+    }
+}
+
+/// Config-file representation of an [`AzureOpenAI`] client, matched by `type = "azure_open_ai"`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AzureOpenAIConfig {
+    pub api_key: String,
+    pub resource_name: String,
+    pub deployment_id: String,
+    pub api_version: String,
+}
+
+impl ClientConfigBuild for AzureOpenAIConfig {
+    type Client = AzureOpenAI;
+
+    fn build(self) -> Result<AzureOpenAI, OpenAIError> {
+        AzureOpenAI::new(
+            self.api_key,
+            self.resource_name,
+            self.deployment_id,
+            self.api_version,
+        )
+    }
+}
@@ -0,0 +1,2 @@
+pub mod azure;
+pub mod openai;
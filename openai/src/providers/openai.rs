@@ -0,0 +1,92 @@
+use crate::client::{ClientBase, ClientConfigBuild, ClientOptions, ReqClient, ORGANIZATION_HEADER};
+use crate::core::response_wrapper::OpenAIError;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use reqwest::header::HeaderMap;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fmt::Debug;
+
+pub struct OpenAI {
+    base: ClientBase,
+    pub org_id: Option<String>,
+}
+
+impl OpenAI {
+    pub fn new(api_key: String, org_id: Option<String>) -> Result<Self, OpenAIError> {
+        Self::with_options(api_key, org_id, ClientOptions::default())
+    }
+
+    pub fn with_options(
+        api_key: String,
+        org_id: Option<String>,
+        options: ClientOptions,
+    ) -> Result<Self, OpenAIError> {
+        let base_url = "https://api.openai.com/v1".to_string();
+        Ok(Self {
+            base: ClientBase::with_options(api_key, base_url, options)?,
+            org_id,
+        })
+    }
+}
+
+#[async_trait]
+impl ReqClient for OpenAI {
+    fn headers(&self) -> HeaderMap {
+        let mut headers = self.base.headers();
+        if let Some(org_id) = &self.org_id {
+            headers.insert(ORGANIZATION_HEADER, org_id.parse().unwrap());
+        }
+        headers
+    }
+
+    fn api_key(&self) -> &str {
+        self.base.api_key()
+    }
+
+    fn api_base(&self) -> String {
+        self.base.api_base().to_string()
+    }
+
+    async fn get<T, F>(&self, route: &str, query: &F) -> Result<T, OpenAIError>
+    where
+        T: DeserializeOwned + Debug + Send,
+        F: Serialize + Send + Sync,
+    {
+        self.base.get(route, query).await
+    }
+
+    async fn post<T, F>(&self, route: &str, json: &F) -> Result<T, OpenAIError>
+    where
+        T: DeserializeOwned + Debug + Send,
+        F: Serialize + Send + Sync,
+    {
+        self.base.post(route, json).await
+    }
+
+    async fn post_stream<T, F>(
+        &self,
+        route: &str,
+        json: &F,
+    ) -> Result<BoxStream<'static, Result<T, OpenAIError>>, OpenAIError>
+    where
+        T: DeserializeOwned + Debug + Send + 'static,
+        F: Serialize + Send + Sync,
+    {
+        self.base.post_stream(route, json).await
+    }
+}
+
+/// Config-file representation of an [`OpenAI`] client, matched by `type = "open_ai"`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenAIConfig {
+    pub api_key: String,
+    pub org_id: Option<String>,
+}
+
+impl ClientConfigBuild for OpenAIConfig {
+    type Client = OpenAI;
+
+    fn build(self) -> Result<OpenAI, OpenAIError> {
+        OpenAI::new(self.api_key, self.org_id)
+    }
+}
@@ -0,0 +1,76 @@
+use tiktoken_rs::CoreBPE;
+
+/// Returns the BPE encoding tiktoken uses for `model`'s family, falling back to
+/// `cl100k_base` (the encoding shared by the `gpt-3.5-turbo`/`gpt-4` families) for
+/// unrecognized models rather than failing outright.
+fn bpe_for_model(model: &str) -> CoreBPE {
+    tiktoken_rs::get_bpe_from_model(model)
+        .unwrap_or_else(|_| tiktoken_rs::cl100k_base().expect("cl100k_base is always available"))
+}
+
+/// Counts the BPE tokens `text` would cost under `model`'s encoding.
+pub fn count_tokens(model: &str, text: &str) -> usize {
+    bpe_for_model(model).encode_ordinary(text).len()
+}
+
+/// Per-message token overhead charged on top of role/content/name tokens, as
+/// documented by OpenAI for chat completions: `(tokens_per_message, tokens_per_name)`.
+fn message_overhead(model: &str) -> (i64, i64) {
+    if model.starts_with("gpt-3.5-turbo-0301") {
+        (4, -1)
+    } else {
+        (3, 1)
+    }
+}
+
+/// Counts the tokens a chat completion prompt would cost, given each message's
+/// `(role, content, name)`, including the per-message overhead and the trailing
+/// assistant-reply priming tokens.
+pub fn count_prompt_tokens(model: &str, messages: &[(String, String, Option<String>)]) -> usize {
+    let (tokens_per_message, tokens_per_name) = message_overhead(model);
+    let mut total: i64 = 3;
+    for (role, content, name) in messages {
+        total += tokens_per_message;
+        total += count_tokens(model, role) as i64;
+        total += count_tokens(model, content) as i64;
+        if let Some(name) = name {
+            total += tokens_per_name;
+            total += count_tokens(model, name) as i64;
+        }
+    }
+    total.max(0) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_prompt_tokens_matches_hand_computed_value_for_default_model() {
+        let messages = vec![
+            (
+                "system".to_string(),
+                "You are a helpful assistant.".to_string(),
+                None,
+            ),
+            ("user".to_string(), "Hello there!".to_string(), None),
+        ];
+
+        // 3 (priming) + 2 * (3 tokens_per_message + 1 role + content tokens)
+        // = 3 + (3 + 1 + 6) + (3 + 1 + 3) = 20
+        assert_eq!(count_prompt_tokens("gpt-4", &messages), 20);
+    }
+
+    #[test]
+    fn count_prompt_tokens_matches_hand_computed_value_for_0301_model_with_name() {
+        let messages = vec![(
+            "user".to_string(),
+            "Hello there!".to_string(),
+            Some("Alice".to_string()),
+        )];
+
+        // 3 (priming) + 4 tokens_per_message + 1 role + 3 content - 1 tokens_per_name + 1 name
+        // = 3 + 4 + 1 + 3 - 1 + 1 = 11
+        assert_eq!(count_prompt_tokens("gpt-3.5-turbo-0301", &messages), 11);
+    }
+}